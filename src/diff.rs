@@ -0,0 +1,179 @@
+use crate::{ComponentRow, CsvRecord};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use tabled::Tabled;
+
+/// How a component's presence changed between the old and new manifest
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Unchanged,
+}
+
+impl ChangeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "Added",
+            ChangeKind::Removed => "Removed",
+            ChangeKind::Unchanged => "Unchanged",
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A [`ComponentRow`] annotated with how it changed between two manifests
+#[derive(Tabled, Debug, PartialEq, Serialize)]
+pub struct DiffRow {
+    #[tabled(rename = "Status")]
+    pub status: ChangeKind,
+    #[tabled(rename = "Type")]
+    #[serde(rename = "type")]
+    pub metadata_type: String,
+    #[tabled(rename = "Parent")]
+    pub parent: String,
+    #[tabled(rename = "Member")]
+    pub member: String,
+}
+
+impl CsvRecord for DiffRow {
+    fn header() -> &'static [&'static str] {
+        &["Status", "Type", "Parent", "Member"]
+    }
+
+    fn fields(&self) -> Vec<&str> {
+        vec![
+            self.status.as_str(),
+            &self.metadata_type,
+            &self.parent,
+            &self.member,
+        ]
+    }
+}
+
+fn key(row: &ComponentRow) -> (&str, &str, &str) {
+    (&row.metadata_type, &row.parent, &row.member)
+}
+
+/// Compare components from two manifests, reporting each as Added, Removed or Unchanged
+///
+/// Rows are keyed on `(type, parent, member)`; `only_changes` drops Unchanged rows.
+pub fn diff_components(
+    old_rows: &[ComponentRow],
+    new_rows: &[ComponentRow],
+    only_changes: bool,
+) -> Vec<DiffRow> {
+    let old_keys: HashSet<(&str, &str, &str)> = old_rows.iter().map(key).collect();
+    let new_keys: HashSet<(&str, &str, &str)> = new_rows.iter().map(key).collect();
+
+    let mut rows = Vec::new();
+
+    for row in new_rows {
+        let status = if old_keys.contains(&key(row)) {
+            ChangeKind::Unchanged
+        } else {
+            ChangeKind::Added
+        };
+        if only_changes && status == ChangeKind::Unchanged {
+            continue;
+        }
+        rows.push(DiffRow {
+            status,
+            metadata_type: row.metadata_type.clone(),
+            parent: row.parent.clone(),
+            member: row.member.clone(),
+        });
+    }
+
+    for row in old_rows {
+        if !new_keys.contains(&key(row)) {
+            rows.push(DiffRow {
+                status: ChangeKind::Removed,
+                metadata_type: row.metadata_type.clone(),
+                parent: row.parent.clone(),
+                member: row.member.clone(),
+            });
+        }
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(metadata_type: &str, parent: &str, member: &str) -> ComponentRow {
+        ComponentRow {
+            metadata_type: metadata_type.to_string(),
+            parent: parent.to_string(),
+            member: member.to_string(),
+        }
+    }
+
+    #[test]
+    fn reports_added_component() {
+        let old = vec![];
+        let new = vec![row("ApexClass", "", "MyClass")];
+        let rows = diff_components(&old, &new, false);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].status, ChangeKind::Added);
+    }
+
+    #[test]
+    fn reports_removed_component() {
+        let old = vec![row("ApexClass", "", "MyClass")];
+        let new = vec![];
+        let rows = diff_components(&old, &new, false);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].status, ChangeKind::Removed);
+    }
+
+    #[test]
+    fn reports_unchanged_component() {
+        let old = vec![row("ApexClass", "", "MyClass")];
+        let new = vec![row("ApexClass", "", "MyClass")];
+        let rows = diff_components(&old, &new, false);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].status, ChangeKind::Unchanged);
+    }
+
+    #[test]
+    fn only_changes_drops_unchanged_rows() {
+        let old = vec![row("ApexClass", "", "MyClass")];
+        let new = vec![
+            row("ApexClass", "", "MyClass"),
+            row("ApexTrigger", "", "MyTrigger"),
+        ];
+        let rows = diff_components(&old, &new, true);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].status, ChangeKind::Added);
+        assert_eq!(rows[0].member, "MyTrigger");
+    }
+
+    #[test]
+    fn mixed_diff_reports_all_three_kinds() {
+        let old = vec![row("ApexClass", "", "Keep"), row("ApexClass", "", "Drop")];
+        let new = vec![row("ApexClass", "", "Keep"), row("ApexClass", "", "New")];
+        let rows = diff_components(&old, &new, false);
+        assert_eq!(rows.len(), 3);
+        assert!(
+            rows.iter()
+                .any(|r| r.member == "Keep" && r.status == ChangeKind::Unchanged)
+        );
+        assert!(
+            rows.iter()
+                .any(|r| r.member == "New" && r.status == ChangeKind::Added)
+        );
+        assert!(
+            rows.iter()
+                .any(|r| r.member == "Drop" && r.status == ChangeKind::Removed)
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Crate-wide error type covering I/O, parsing and output failures
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    XmlParse {
+        path: PathBuf,
+        source: quick_xml::DeError,
+    },
+    CsvWrite(csv::Error),
+    FileRead {
+        path: PathBuf,
+        source: io::Error,
+    },
+    FilterParse(String),
+    JsonWrite(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{e}"),
+            Error::XmlParse { path, source } => {
+                write!(f, "Failed to parse {}: {source}", path.display())
+            }
+            Error::CsvWrite(e) => write!(f, "{e}"),
+            Error::FileRead { path, source } => {
+                write!(f, "Failed to parse {}: {source}", path.display())
+            }
+            Error::FilterParse(msg) => write!(f, "Failed to parse filter expression: {msg}"),
+            Error::JsonWrite(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::XmlParse { source, .. } => Some(source),
+            Error::CsvWrite(e) => Some(e),
+            Error::FileRead { source, .. } => Some(source),
+            Error::FilterParse(_) => None,
+            Error::JsonWrite(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Error::CsvWrite(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::JsonWrite(e)
+    }
+}
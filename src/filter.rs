@@ -0,0 +1,435 @@
+use crate::ComponentRow;
+use crate::error::Error;
+
+/// Fields a filter expression may compare against
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Field {
+    Type,
+    Parent,
+    Member,
+}
+
+impl Field {
+    fn from_ident(ident: &str) -> Option<Self> {
+        match ident {
+            "type" => Some(Field::Type),
+            "parent" => Some(Field::Parent),
+            "member" => Some(Field::Member),
+            _ => None,
+        }
+    }
+
+    fn value(self, row: &ComponentRow) -> &str {
+        match self {
+            Field::Type => &row.metadata_type,
+            Field::Parent => &row.parent,
+            Field::Member => &row.member,
+        }
+    }
+}
+
+/// Comparison operators supported by a filter expression
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum CmpOp {
+    Eq,
+    Ne,
+    Glob,
+}
+
+/// Parsed `--filter` expression, evaluated against a [`ComponentRow`]
+#[derive(Debug)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp {
+        field: Field,
+        op: CmpOp,
+        literal: String,
+    },
+}
+
+impl Expr {
+    /// Evaluate this expression against a single row
+    pub fn eval(&self, row: &ComponentRow) -> bool {
+        match self {
+            Expr::And(lhs, rhs) => lhs.eval(row) && rhs.eval(row),
+            Expr::Or(lhs, rhs) => lhs.eval(row) || rhs.eval(row),
+            Expr::Not(inner) => !inner.eval(row),
+            Expr::Cmp { field, op, literal } => {
+                let value = field.value(row);
+                match op {
+                    CmpOp::Eq => value == literal,
+                    CmpOp::Ne => value != literal,
+                    CmpOp::Glob => glob_match(literal, value),
+                }
+            }
+        }
+    }
+}
+
+/// Parse a `--filter` expression into an [`Expr`]
+pub fn parse(input: &str) -> Result<Expr, Error> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+    };
+    let expr = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(tok) => Err(Error::FilterParse(format!(
+            "unexpected trailing token `{tok}`"
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Eq,
+    Ne,
+    Glob,
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(s) => write!(f, "{s}"),
+            Token::Str(s) => write!(f, "\"{s}\""),
+            Token::Eq => write!(f, "=="),
+            Token::Ne => write!(f, "!="),
+            Token::Glob => write!(f, "~"),
+            Token::And => write!(f, "and"),
+            Token::Or => write!(f, "or"),
+            Token::Not => write!(f, "not"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '~' => {
+                chars.next();
+                tokens.push(Token::Glob);
+            }
+            '=' => {
+                chars.next();
+                if chars.next_if_eq(&'=').is_some() {
+                    tokens.push(Token::Eq);
+                } else {
+                    return Err(Error::FilterParse("expected `==`".to_string()));
+                }
+            }
+            '!' => {
+                chars.next();
+                if chars.next_if_eq(&'=').is_some() {
+                    tokens.push(Token::Ne);
+                } else {
+                    return Err(Error::FilterParse("expected `!=`".to_string()));
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut literal = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => literal.push(c),
+                        None => {
+                            return Err(Error::FilterParse(
+                                "unterminated string literal".to_string(),
+                            ));
+                        }
+                    }
+                }
+                tokens.push(Token::Str(literal));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        ident.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(match ident.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(ident),
+                });
+            }
+            c => {
+                return Err(Error::FilterParse(format!("unexpected character `{c}`")));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<&Token> {
+        let tok = self.tokens.get(self.pos);
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<(), Error> {
+        match self.bump() {
+            Some(tok) if tok == token => Ok(()),
+            Some(tok) => Err(Error::FilterParse(format!(
+                "expected `{token}`, found `{tok}`"
+            ))),
+            None => Err(Error::FilterParse(format!(
+                "expected `{token}`, found end of expression"
+            ))),
+        }
+    }
+
+    // `or` has the lowest binding power
+    fn parse_or(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.bump();
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    // `and` binds tighter than `or`
+    fn parse_and(&mut self) -> Result<Expr, Error> {
+        let mut lhs = self.parse_unary()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.bump();
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, Error> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.bump();
+            let inner = self.parse_unary()?;
+            Ok(Expr::Not(Box::new(inner)))
+        } else {
+            self.parse_primary()
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, Error> {
+        match self.bump().cloned() {
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                self.expect(&Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(ident)) => {
+                let field = Field::from_ident(&ident).ok_or_else(|| {
+                    Error::FilterParse(format!(
+                        "unknown field `{ident}` (expected `type`, `parent` or `member`)"
+                    ))
+                })?;
+                let op = match self.bump() {
+                    Some(Token::Eq) => CmpOp::Eq,
+                    Some(Token::Ne) => CmpOp::Ne,
+                    Some(Token::Glob) => CmpOp::Glob,
+                    Some(tok) => {
+                        return Err(Error::FilterParse(format!(
+                            "expected a comparison operator, found `{tok}`"
+                        )));
+                    }
+                    None => {
+                        return Err(Error::FilterParse(
+                            "expected a comparison operator, found end of expression".to_string(),
+                        ));
+                    }
+                };
+                let literal = match self.bump() {
+                    Some(Token::Str(s)) => s.clone(),
+                    Some(tok) => {
+                        return Err(Error::FilterParse(format!(
+                            "expected a string literal, found `{tok}`"
+                        )));
+                    }
+                    None => {
+                        return Err(Error::FilterParse(
+                            "expected a string literal, found end of expression".to_string(),
+                        ));
+                    }
+                };
+                Ok(Expr::Cmp { field, op, literal })
+            }
+            Some(tok) => Err(Error::FilterParse(format!("unexpected token `{tok}`"))),
+            None => Err(Error::FilterParse(
+                "unexpected end of expression".to_string(),
+            )),
+        }
+    }
+}
+
+/// Match `value` against a glob `pattern` where `*` matches any run of characters
+/// and `?` matches exactly one character
+///
+/// Uses the standard iterative two-pointer algorithm (tracking the most recent `*`
+/// and the value position it last matched from) rather than naive backtracking, so
+/// patterns with many `*`s stay O(pattern.len() * value.len()) instead of exponential.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    let (mut p, mut v) = (0, 0);
+    let mut star: Option<usize> = None;
+    let mut star_v = 0;
+
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some(p);
+            star_v = v;
+            p += 1;
+        } else if let Some(star_p) = star {
+            p = star_p + 1;
+            star_v += 1;
+            v = star_v;
+        } else {
+            return false;
+        }
+    }
+
+    while p < pattern.len() && pattern[p] == '*' {
+        p += 1;
+    }
+    p == pattern.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(metadata_type: &str, parent: &str, member: &str) -> ComponentRow {
+        ComponentRow {
+            metadata_type: metadata_type.to_string(),
+            parent: parent.to_string(),
+            member: member.to_string(),
+        }
+    }
+
+    #[test]
+    fn eq_matches_exact_value() {
+        let expr = parse(r#"type == "CustomField""#).unwrap();
+        assert!(expr.eval(&row("CustomField", "Account", "Active__c")));
+        assert!(!expr.eval(&row("ApexClass", "", "MyClass")));
+    }
+
+    #[test]
+    fn ne_excludes_matching_value() {
+        let expr = parse(r#"type != "ApexClass""#).unwrap();
+        assert!(!expr.eval(&row("ApexClass", "", "MyClass")));
+        assert!(expr.eval(&row("ApexTrigger", "", "MyTrigger")));
+    }
+
+    #[test]
+    fn glob_matches_wildcard() {
+        let expr = parse(r#"parent ~ "Account*""#).unwrap();
+        assert!(expr.eval(&row("CustomField", "Account", "Active__c")));
+        assert!(expr.eval(&row("CustomField", "AccountHistory", "Field")));
+        assert!(!expr.eval(&row("CustomField", "Contact", "Field")));
+    }
+
+    #[test]
+    fn glob_match_handles_many_wildcards_without_blowing_up() {
+        // Pathological for naive recursive backtracking (exponential); must resolve
+        // instantly under the iterative two-pointer algorithm.
+        let pattern = "*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*a*b";
+        let value = "a".repeat(35);
+        assert!(!glob_match(pattern, &value));
+    }
+
+    #[test]
+    fn and_requires_both_sides() {
+        let expr = parse(r#"type == "CustomField" and parent == "Account""#).unwrap();
+        assert!(expr.eval(&row("CustomField", "Account", "Active__c")));
+        assert!(!expr.eval(&row("CustomField", "Contact", "Active__c")));
+    }
+
+    #[test]
+    fn or_requires_either_side() {
+        let expr = parse(r#"type == "ApexClass" or type == "ApexTrigger""#).unwrap();
+        assert!(expr.eval(&row("ApexClass", "", "MyClass")));
+        assert!(expr.eval(&row("ApexTrigger", "", "MyTrigger")));
+        assert!(!expr.eval(&row("CustomField", "", "Field")));
+    }
+
+    #[test]
+    fn not_negates_primary() {
+        let expr = parse(r#"not type == "ApexClass""#).unwrap();
+        assert!(!expr.eval(&row("ApexClass", "", "MyClass")));
+        assert!(expr.eval(&row("ApexTrigger", "", "MyTrigger")));
+    }
+
+    #[test]
+    fn parens_override_precedence() {
+        let expr = parse(r#"type == "A" and (type == "B" or type == "A")"#).unwrap();
+        assert!(expr.eval(&row("A", "", "")));
+
+        // Without the parens this would read `type == "A" or (type == "B" and member == "x")`
+        let expr = parse(r#"(type == "A" or type == "B") and member == "x""#).unwrap();
+        assert!(expr.eval(&row("A", "", "x")));
+        assert!(!expr.eval(&row("A", "", "y")));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let expr = parse(r#"type == "A" or type == "B" and type == "C""#).unwrap();
+        // Equivalent to: type == "A" or (type == "B" and type == "C")
+        assert!(expr.eval(&row("A", "", "")));
+        assert!(!expr.eval(&row("B", "", "")));
+    }
+
+    #[test]
+    fn malformed_expression_is_a_parse_error() {
+        assert!(parse(r#"type == "#).is_err());
+        assert!(parse(r#"type "CustomField""#).is_err());
+        assert!(parse(r#"unknown == "x""#).is_err());
+    }
+}
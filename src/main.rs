@@ -1,14 +1,35 @@
-use clap::{Parser, ValueEnum};
-use serde::Deserialize;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use diff::DiffRow;
+use error::Error;
+use filter::Expr;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{self, Write};
 use std::path::PathBuf;
 use tabled::{Table, Tabled, settings::Style};
 
+mod diff;
+mod error;
+mod filter;
+
 /// Salesforce package.xml viewer - displays metadata components in readable formats
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Display metadata components from a single package.xml (default)
+    View(ViewArgs),
+    /// Compare two package.xml manifests and report added/removed/unchanged components
+    Diff(DiffArgs),
+}
+
+#[derive(Args)]
+struct ViewArgs {
     /// Path to the package.xml file
     path: PathBuf,
 
@@ -23,6 +44,41 @@ struct Cli {
     /// Disable splitting Parent.Member format into separate columns
     #[arg(long, default_value_t = false)]
     no_split_parent: bool,
+
+    /// Filter expression over `type`, `parent` and `member`, e.g.
+    /// `type == "CustomField" and parent ~ "Account*"`
+    #[arg(long)]
+    filter: Option<String>,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Path to the old package.xml file
+    old: PathBuf,
+
+    /// Path to the new package.xml file
+    new: PathBuf,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    /// Sort order
+    #[arg(short, long, value_enum, default_value_t = SortOrder::ByType)]
+    sort: SortOrder,
+
+    /// Disable splitting Parent.Member format into separate columns
+    #[arg(long, default_value_t = false)]
+    no_split_parent: bool,
+
+    /// Filter expression over `type`, `parent` and `member`, e.g.
+    /// `type == "CustomField" and parent ~ "Account*"`
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// Only show Added and Removed rows, suppressing Unchanged ones
+    #[arg(long, default_value_t = false)]
+    only_changes: bool,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -30,6 +86,8 @@ enum OutputFormat {
     Table,
     Csv,
     Tsv,
+    Json,
+    Ndjson,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -53,9 +111,10 @@ struct Types {
 }
 
 // Output structure
-#[derive(Tabled, Debug, PartialEq)]
+#[derive(Tabled, Debug, PartialEq, Serialize)]
 struct ComponentRow {
     #[tabled(rename = "Type")]
+    #[serde(rename = "type")]
     metadata_type: String,
     #[tabled(rename = "Parent")]
     parent: String,
@@ -63,9 +122,15 @@ struct ComponentRow {
     member: String,
 }
 
-fn parse_package_xml(path: &PathBuf) -> Result<Package, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(path)?;
-    let package: Package = quick_xml::de::from_str(&content)?;
+fn parse_package_xml(path: &PathBuf) -> Result<Package, Error> {
+    let content = fs::read_to_string(path).map_err(|source| Error::FileRead {
+        path: path.clone(),
+        source,
+    })?;
+    let package: Package = quick_xml::de::from_str(&content).map_err(|source| Error::XmlParse {
+        path: path.clone(),
+        source,
+    })?;
     Ok(package)
 }
 
@@ -87,6 +152,7 @@ fn flatten_components(
     package: &Package,
     sort_order: &SortOrder,
     split_parent: bool,
+    filter: Option<&Expr>,
 ) -> Vec<ComponentRow> {
     let mut rows: Vec<ComponentRow> = package
         .types
@@ -117,6 +183,10 @@ fn flatten_components(
         })
         .collect();
 
+    if let Some(expr) = filter {
+        rows.retain(|row| expr.eval(row));
+    }
+
     if matches!(sort_order, SortOrder::ByType) {
         rows.sort_by(|a, b| {
             a.metadata_type
@@ -129,70 +199,163 @@ fn flatten_components(
     rows
 }
 
-fn output_table(rows: &[ComponentRow]) -> Result<(), Box<dyn std::error::Error>> {
+/// A row that can be rendered as a CSV/TSV record alongside its column headers
+trait CsvRecord {
+    fn header() -> &'static [&'static str];
+    fn fields(&self) -> Vec<&str>;
+}
+
+impl CsvRecord for ComponentRow {
+    fn header() -> &'static [&'static str] {
+        &["Type", "Parent", "Member"]
+    }
+
+    fn fields(&self) -> Vec<&str> {
+        vec![&self.metadata_type, &self.parent, &self.member]
+    }
+}
+
+fn output_table<T: Tabled>(rows: &[T]) -> Result<(), Error> {
     let mut table = Table::new(rows);
     table.with(Style::modern());
     writeln!(io::stdout(), "{}", table)?;
     Ok(())
 }
 
-fn output_csv<W: Write>(
-    rows: &[ComponentRow],
-    writer: W,
-) -> Result<(), Box<dyn std::error::Error>> {
+fn output_csv<T: CsvRecord, W: Write>(rows: &[T], writer: W) -> Result<(), Error> {
     let mut wtr = csv::Writer::from_writer(writer);
     // Always write header, even if rows is empty
-    wtr.write_record(["Type", "Parent", "Member"])?;
+    wtr.write_record(T::header())?;
     for row in rows {
-        wtr.write_record([&row.metadata_type, &row.parent, &row.member])?;
+        wtr.write_record(row.fields())?;
     }
     wtr.flush()?;
     Ok(())
 }
 
-fn output_tsv<W: Write>(
-    rows: &[ComponentRow],
-    writer: W,
-) -> Result<(), Box<dyn std::error::Error>> {
+fn output_tsv<T: CsvRecord, W: Write>(rows: &[T], writer: W) -> Result<(), Error> {
     let mut wtr = csv::WriterBuilder::new()
         .delimiter(b'\t')
         .from_writer(writer);
     // Always write header, even if rows is empty
-    wtr.write_record(["Type", "Parent", "Member"])?;
+    wtr.write_record(T::header())?;
     for row in rows {
-        wtr.write_record([&row.metadata_type, &row.parent, &row.member])?;
+        wtr.write_record(row.fields())?;
     }
     wtr.flush()?;
     Ok(())
 }
 
-fn main() {
-    let args = Cli::parse();
+fn output_json<T: Serialize, W: Write>(rows: &[T], mut writer: W) -> Result<(), Error> {
+    let json = serde_json::to_string_pretty(rows)?;
+    writeln!(writer, "{json}")?;
+    Ok(())
+}
 
-    let package = match parse_package_xml(&args.path) {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!("Error: Failed to parse {}: {}", args.path.display(), e);
-            std::process::exit(1);
-        }
-    };
+fn output_ndjson<T: Serialize, W: Write>(rows: &[T], mut writer: W) -> Result<(), Error> {
+    for row in rows {
+        let json = serde_json::to_string(row)?;
+        writeln!(writer, "{json}")?;
+    }
+    Ok(())
+}
+
+/// Tokens that select a subcommand (or a top-level help/version flag) explicitly;
+/// anything else in the first argument position belongs to the default `view`
+/// subcommand's own arguments, whether that's a path or a `view`-specific flag.
+const KNOWN_SUBCOMMAND_TOKENS: &[&str] =
+    &["view", "diff", "help", "--help", "-h", "--version", "-V"];
+
+/// Insert the default `view` subcommand when the first argument isn't a known
+/// subcommand or top-level flag, so `changeset-component-viewer package.xml` and
+/// `changeset-component-viewer --format csv package.xml` both keep working.
+fn insert_default_subcommand(mut args: Vec<std::ffi::OsString>) -> Vec<std::ffi::OsString> {
+    let is_known_token = args.get(1).is_some_and(|arg| {
+        KNOWN_SUBCOMMAND_TOKENS
+            .iter()
+            .any(|token| arg == std::ffi::OsStr::new(token))
+    });
+    if args.get(1).is_some() && !is_known_token {
+        args.insert(1, "view".into());
+    }
+    args
+}
+
+fn args_with_default_subcommand() -> Vec<std::ffi::OsString> {
+    insert_default_subcommand(std::env::args_os().collect())
+}
+
+fn parse_filter(expr: Option<&str>) -> Result<Option<Expr>, Error> {
+    expr.map(filter::parse).transpose()
+}
+
+fn run_view(args: ViewArgs) -> Result<(), Error> {
+    let package = parse_package_xml(&args.path)?;
+    let filter = parse_filter(args.filter.as_deref())?;
+    let rows = flatten_components(&package, &args.sort, !args.no_split_parent, filter.as_ref());
+
+    match args.format {
+        OutputFormat::Table => output_table(&rows),
+        OutputFormat::Csv => output_csv(&rows, io::stdout()),
+        OutputFormat::Tsv => output_tsv(&rows, io::stdout()),
+        OutputFormat::Json => output_json(&rows, io::stdout()),
+        OutputFormat::Ndjson => output_ndjson(&rows, io::stdout()),
+    }
+}
 
-    let rows = flatten_components(&package, &args.sort, !args.no_split_parent);
+fn run_diff(args: DiffArgs) -> Result<(), Error> {
+    let old_package = parse_package_xml(&args.old)?;
+    let new_package = parse_package_xml(&args.new)?;
+    let filter = parse_filter(args.filter.as_deref())?;
+
+    let old_rows = flatten_components(
+        &old_package,
+        &args.sort,
+        !args.no_split_parent,
+        filter.as_ref(),
+    );
+    let new_rows = flatten_components(
+        &new_package,
+        &args.sort,
+        !args.no_split_parent,
+        filter.as_ref(),
+    );
+
+    let mut rows: Vec<DiffRow> = diff::diff_components(&old_rows, &new_rows, args.only_changes);
+    if matches!(args.sort, SortOrder::ByType) {
+        rows.sort_by(|a, b| {
+            a.metadata_type
+                .cmp(&b.metadata_type)
+                .then_with(|| a.parent.cmp(&b.parent))
+                .then_with(|| a.member.cmp(&b.member))
+        });
+    }
 
-    let result = match args.format {
+    match args.format {
         OutputFormat::Table => output_table(&rows),
         OutputFormat::Csv => output_csv(&rows, io::stdout()),
         OutputFormat::Tsv => output_tsv(&rows, io::stdout()),
+        OutputFormat::Json => output_json(&rows, io::stdout()),
+        OutputFormat::Ndjson => output_ndjson(&rows, io::stdout()),
+    }
+}
+
+fn main() {
+    let args = Cli::parse_from(args_with_default_subcommand());
+
+    let result = match args.command {
+        Command::View(view_args) => run_view(view_args),
+        Command::Diff(diff_args) => run_diff(diff_args),
     };
 
     if let Err(e) = result {
         // Check if it's a broken pipe error
-        if let Some(io_err) = e.downcast_ref::<io::Error>()
+        if let Error::Io(ref io_err) = e
             && io_err.kind() == io::ErrorKind::BrokenPipe
         {
             return;
         }
-        eprintln!("Error writing output: {}", e);
+        eprintln!("Error: {e}");
         std::process::exit(1);
     }
 }
@@ -219,21 +382,21 @@ mod tests {
     #[test]
     fn flatten_empty_package() {
         let package = make_package(vec![]);
-        let rows = flatten_components(&package, &SortOrder::ByType, false);
+        let rows = flatten_components(&package, &SortOrder::ByType, false, None);
         assert!(rows.is_empty());
     }
 
     #[test]
     fn flatten_empty_types() {
         let package = make_package(vec![("ApexClass", vec![])]);
-        let rows = flatten_components(&package, &SortOrder::ByType, false);
+        let rows = flatten_components(&package, &SortOrder::ByType, false, None);
         assert!(rows.is_empty());
     }
 
     #[test]
     fn flatten_single_type_single_member() {
         let package = make_package(vec![("ApexClass", vec!["MyClass"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, false);
+        let rows = flatten_components(&package, &SortOrder::AsIs, false, None);
         assert_eq!(rows.len(), 1);
         assert_eq!(
             rows[0],
@@ -248,7 +411,7 @@ mod tests {
     #[test]
     fn flatten_single_type_multiple_members() {
         let package = make_package(vec![("ApexClass", vec!["ClassA", "ClassB", "ClassC"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, false);
+        let rows = flatten_components(&package, &SortOrder::AsIs, false, None);
         assert_eq!(rows.len(), 3);
         assert_eq!(rows[0].member, "ClassA");
         assert_eq!(rows[1].member, "ClassB");
@@ -261,7 +424,7 @@ mod tests {
             ("ApexClass", vec!["ClassA"]),
             ("ApexTrigger", vec!["TriggerA", "TriggerB"]),
         ]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, false);
+        let rows = flatten_components(&package, &SortOrder::AsIs, false, None);
         assert_eq!(rows.len(), 3);
         assert_eq!(rows[0].metadata_type, "ApexClass");
         assert_eq!(rows[1].metadata_type, "ApexTrigger");
@@ -274,7 +437,7 @@ mod tests {
             ("CustomObject", vec!["Account"]),
             ("ApexClass", vec!["MyClass"]),
         ]);
-        let rows = flatten_components(&package, &SortOrder::ByType, false);
+        let rows = flatten_components(&package, &SortOrder::ByType, false, None);
         assert_eq!(rows[0].metadata_type, "ApexClass");
         assert_eq!(rows[1].metadata_type, "CustomObject");
     }
@@ -285,7 +448,7 @@ mod tests {
             ("CustomObject", vec!["Account"]),
             ("ApexClass", vec!["MyClass"]),
         ]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, false);
+        let rows = flatten_components(&package, &SortOrder::AsIs, false, None);
         assert_eq!(rows[0].metadata_type, "CustomObject");
         assert_eq!(rows[1].metadata_type, "ApexClass");
     }
@@ -293,7 +456,7 @@ mod tests {
     #[test]
     fn flatten_by_type_sorts_members_within_type() {
         let package = make_package(vec![("ApexClass", vec!["Zebra", "Alpha", "Middle"])]);
-        let rows = flatten_components(&package, &SortOrder::ByType, false);
+        let rows = flatten_components(&package, &SortOrder::ByType, false, None);
         assert_eq!(rows[0].member, "Alpha");
         assert_eq!(rows[1].member, "Middle");
         assert_eq!(rows[2].member, "Zebra");
@@ -376,7 +539,7 @@ mod tests {
     #[test]
     fn flatten_splits_parent_for_custom_field() {
         let package = make_package(vec![("CustomField", vec!["Account.Active__c"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, true);
+        let rows = flatten_components(&package, &SortOrder::AsIs, true, None);
         assert_eq!(rows[0].parent, "Account");
         assert_eq!(rows[0].member, "Active__c");
     }
@@ -384,7 +547,7 @@ mod tests {
     #[test]
     fn flatten_splits_parent_for_record_type() {
         let package = make_package(vec![("RecordType", vec!["Metric.Completion"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, true);
+        let rows = flatten_components(&package, &SortOrder::AsIs, true, None);
         assert_eq!(rows[0].parent, "Metric");
         assert_eq!(rows[0].member, "Completion");
     }
@@ -393,7 +556,7 @@ mod tests {
     fn flatten_splits_only_first_dot() {
         // Account.Sub.Field__c â†’ Parent: "Account", Member: "Sub.Field__c"
         let package = make_package(vec![("CustomField", vec!["Account.Sub.Field__c"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, true);
+        let rows = flatten_components(&package, &SortOrder::AsIs, true, None);
         assert_eq!(rows[0].parent, "Account");
         assert_eq!(rows[0].member, "Sub.Field__c");
     }
@@ -401,7 +564,7 @@ mod tests {
     #[test]
     fn flatten_no_split_when_disabled() {
         let package = make_package(vec![("CustomField", vec!["Account.Active__c"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, false);
+        let rows = flatten_components(&package, &SortOrder::AsIs, false, None);
         assert_eq!(rows[0].parent, "");
         assert_eq!(rows[0].member, "Account.Active__c");
     }
@@ -409,7 +572,7 @@ mod tests {
     #[test]
     fn flatten_no_split_for_non_splittable_type() {
         let package = make_package(vec![("ApexClass", vec!["MyClass.Inner"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, true);
+        let rows = flatten_components(&package, &SortOrder::AsIs, true, None);
         assert_eq!(rows[0].parent, "");
         assert_eq!(rows[0].member, "MyClass.Inner");
     }
@@ -425,7 +588,7 @@ mod tests {
             ("SharingOwnerRule", vec!["Lead.Owner_Rule"]),
             ("SharingTerritoryRule", vec!["Account.Territory_Rule"]),
         ]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, true);
+        let rows = flatten_components(&package, &SortOrder::AsIs, true, None);
 
         // All splittable types should have parent populated
         for row in &rows {
@@ -440,7 +603,7 @@ mod tests {
     #[test]
     fn flatten_splits_parent_for_layout_by_hyphen() {
         let package = make_package(vec![("Layout", vec!["Account-Account Layout"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, true);
+        let rows = flatten_components(&package, &SortOrder::AsIs, true, None);
         assert_eq!(rows[0].parent, "Account");
         assert_eq!(rows[0].member, "Account Layout");
     }
@@ -448,7 +611,7 @@ mod tests {
     #[test]
     fn flatten_no_dot_in_member_keeps_empty_parent() {
         let package = make_package(vec![("CustomField", vec!["SomeField"])]);
-        let rows = flatten_components(&package, &SortOrder::AsIs, true);
+        let rows = flatten_components(&package, &SortOrder::AsIs, true, None);
         assert_eq!(rows[0].parent, "");
         assert_eq!(rows[0].member, "SomeField");
     }
@@ -467,4 +630,141 @@ mod tests {
             "Type,Parent,Member\nCustomField,Account,Active__c\n"
         );
     }
+
+    // ==================== output_json tests ====================
+
+    #[test]
+    fn output_json_empty_rows() {
+        let rows: Vec<ComponentRow> = vec![];
+        let mut buffer = Vec::new();
+        output_json(&rows, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn output_json_single_row() {
+        let rows = vec![ComponentRow {
+            metadata_type: "CustomField".to_string(),
+            parent: "Account".to_string(),
+            member: "Active__c".to_string(),
+        }];
+        let mut buffer = Vec::new();
+        output_json(&rows, &mut buffer).unwrap();
+        let expected = "[\n  {\n    \"type\": \"CustomField\",\n    \"parent\": \"Account\",\n    \"member\": \"Active__c\"\n  }\n]\n";
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    }
+
+    // ==================== output_ndjson tests ====================
+
+    #[test]
+    fn output_ndjson_empty_rows() {
+        let rows: Vec<ComponentRow> = vec![];
+        let mut buffer = Vec::new();
+        output_ndjson(&rows, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "");
+    }
+
+    #[test]
+    fn output_ndjson_multiple_rows() {
+        let rows = vec![
+            ComponentRow {
+                metadata_type: "ApexClass".to_string(),
+                parent: String::new(),
+                member: "ClassA".to_string(),
+            },
+            ComponentRow {
+                metadata_type: "CustomField".to_string(),
+                parent: "Account".to_string(),
+                member: "Active__c".to_string(),
+            },
+        ];
+        let mut buffer = Vec::new();
+        output_ndjson(&rows, &mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"type\":\"ApexClass\",\"parent\":\"\",\"member\":\"ClassA\"}\n\
+             {\"type\":\"CustomField\",\"parent\":\"Account\",\"member\":\"Active__c\"}\n"
+        );
+    }
+
+    // ==================== filter tests ====================
+
+    #[test]
+    fn flatten_applies_filter_after_split() {
+        let package = make_package(vec![
+            ("CustomField", vec!["Account.Active__c", "Contact.Phone__c"]),
+            ("ApexClass", vec!["MyClass"]),
+        ]);
+        let expr = filter::parse(r#"parent == "Account""#).unwrap();
+        let rows = flatten_components(&package, &SortOrder::AsIs, true, Some(&expr));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].member, "Active__c");
+    }
+
+    #[test]
+    fn flatten_without_filter_keeps_all_rows() {
+        let package = make_package(vec![("ApexClass", vec!["ClassA", "ClassB"])]);
+        let rows = flatten_components(&package, &SortOrder::AsIs, false, None);
+        assert_eq!(rows.len(), 2);
+    }
+
+    // ==================== insert_default_subcommand tests ====================
+
+    fn os_args(args: &[&str]) -> Vec<std::ffi::OsString> {
+        args.iter().map(std::ffi::OsString::from).collect()
+    }
+
+    #[test]
+    fn default_subcommand_inserted_for_bare_path() {
+        let args = insert_default_subcommand(os_args(&["prog", "package.xml"]));
+        assert_eq!(args, os_args(&["prog", "view", "package.xml"]));
+    }
+
+    #[test]
+    fn default_subcommand_inserted_when_flag_precedes_path() {
+        let args = insert_default_subcommand(os_args(&["prog", "--format", "csv", "package.xml"]));
+        assert_eq!(
+            args,
+            os_args(&["prog", "view", "--format", "csv", "package.xml"])
+        );
+    }
+
+    #[test]
+    fn known_subcommands_are_left_untouched() {
+        for subcommand in ["view", "diff"] {
+            let args = insert_default_subcommand(os_args(&["prog", subcommand, "package.xml"]));
+            assert_eq!(args, os_args(&["prog", subcommand, "package.xml"]));
+        }
+    }
+
+    #[test]
+    fn help_and_version_tokens_are_left_untouched() {
+        for token in ["help", "--help", "-h", "--version", "-V"] {
+            let args = insert_default_subcommand(os_args(&["prog", token]));
+            assert_eq!(args, os_args(&["prog", token]));
+        }
+    }
+
+    #[test]
+    fn no_arguments_is_left_untouched() {
+        let args = insert_default_subcommand(os_args(&["prog"]));
+        assert_eq!(args, os_args(&["prog"]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn default_subcommand_inserted_for_non_utf8_path() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let non_utf8_path = std::ffi::OsString::from_vec(vec![0xFF, 0xFE]);
+        let args = insert_default_subcommand(vec!["prog".into(), non_utf8_path.clone()]);
+        assert_eq!(
+            args,
+            vec![
+                std::ffi::OsString::from("prog"),
+                std::ffi::OsString::from("view"),
+                non_utf8_path,
+            ]
+        );
+    }
 }